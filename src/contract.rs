@@ -1,22 +1,28 @@
 use cosmwasm_std::{
-    entry_point, to_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Response,
-    StdResult,
+    entry_point, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env,
+    MessageInfo, Order, Response, StdResult, Uint128, WasmMsg,
 };
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Config, Loan, LOANS};
+use crate::msg::{ArbiterResponse, ExecuteMsg, InstantiateMsg, ListLoansResponse, QueryMsg};
+use crate::state::{AssetKind, Config, Loan, CONFIG, LOANS};
 use cw2::set_contract_version;
-use cw20::Cw20ExecuteMsg;
+use cw20::{AllowanceResponse, Cw20ExecuteMsg, Cw20QueryMsg};
+use cw721::Cw721ExecuteMsg;
+use cw_storage_plus::Bound;
 
 // Version info, for migration info
 const CONTRACT_NAME: &str = "RWA Lending and Borrowing Contract";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+const SECONDS_PER_DAY: u64 = 86_400;
+const DEFAULT_LIMIT: u32 = 30;
+const MAX_LIMIT: u32 = 30;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
@@ -48,20 +54,24 @@ pub fn execute(
     match msg {
         ExecuteMsg::LendToken {
             asset_address,
+            asset_kind,
             duration,
             collateral_amount,
             daily_fee_amount,
             max_rent_days,
+            grace_period,
             ipfs_cid,
         } => execute_lend_token(
             deps,
             env,
             info,
             asset_address,
+            asset_kind,
             duration,
             collateral_amount,
             daily_fee_amount,
             max_rent_days,
+            grace_period,
             ipfs_cid,
         ),
         ExecuteMsg::BorrowToken { asset_address } => {
@@ -73,26 +83,42 @@ pub fn execute(
         ExecuteMsg::WithdrawCollateral { amount } => {
             execute_withdraw_collateral(deps, env, info, amount)
         }
+        ExecuteMsg::LiquidateLoan { asset_address } => {
+            execute_liquidate_loan(deps, env, info, asset_address)
+        }
     }
 }
 
 fn execute_lend_token(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     asset_address: Addr,
+    asset_kind: AssetKind,
     duration: u64,
     collateral_amount: Vec<Coin>,
     daily_fee_amount: Vec<Coin>,
     max_rent_days: u64,
+    grace_period: u64,
     ipfs_cid: String,
 ) -> Result<Response, ContractError> {
-    // Retrieve the toke from contract
+    // Create the loan on first lend, or load it back up for a repeat lend of the same asset
     let loan: Loan = match LOANS.may_load(deps.storage, &asset_address)? {
         Some(loan) => loan,
-        None => {
-            return Err(ContractError::LoanNotFound {});
-        }
+        None => Loan {
+            lender: info.sender.clone(),
+            borrower: Addr::unchecked(""),
+            asset_address: asset_address.clone(),
+            asset_kind: asset_kind.clone(),
+            duration,
+            collateral_amount: collateral_amount.clone(),
+            daily_fee_amount: daily_fee_amount.clone(),
+            max_rent_days,
+            grace_period,
+            ipfs_cid: ipfs_cid.clone(),
+            start_time: 0,
+            is_active: false,
+        },
     };
 
     // Ensure that the lender is the caller of this function
@@ -100,8 +126,8 @@ fn execute_lend_token(
         return Err(ContractError::Unauthorized {});
     }
 
-    // Ensure that the token is not already lended
-    if loan.borrower != Addr::unchecked("") {
+    // Ensure that an identical active loan doesn't already hold this asset
+    if loan.is_active {
         return Err(ContractError::LoanAlreadyActive {});
     }
 
@@ -125,20 +151,64 @@ fn execute_lend_token(
         lender: loan.lender,
         borrower: loan.borrower,
         asset_address: loan.asset_address,
+        asset_kind,
         duration,
         collateral_amount: collateral_amount.clone(),
         daily_fee_amount: daily_fee_amount.clone(),
         max_rent_days,
+        grace_period,
         ipfs_cid,
-        start_time: loan.start_time,
+        start_time: env.block.time.seconds(),
+        is_active: true,
     };
 
     // Save the updated loan to state
     LOANS.save(deps.storage, &asset_address, &updated_loan)?;
 
-    // Transfer the asset to the contract
-    // Return a response indicating success
-    Ok(Response::default())
+    // Genuinely escrow the asset into the contract. The lender must have pre-approved
+    // the contract (a cw20 allowance, or a cw721 operator approval) beforehand. A missing
+    // cw20 allowance is caught here; a missing cw721 approval makes the NFT contract
+    // itself reject the sub-message.
+    let escrow_msg = match &updated_loan.asset_kind {
+        AssetKind::Cw20 { amount } => {
+            // Check the allowance up front so a missing/insufficient approval surfaces as
+            // our own, clearer error instead of an opaque failure from the cw20 contract
+            let allowance: AllowanceResponse = deps.querier.query_wasm_smart(
+                updated_loan.asset_address.to_string(),
+                &Cw20QueryMsg::Allowance {
+                    owner: info.sender.to_string(),
+                    spender: env.contract.address.to_string(),
+                },
+            )?;
+            if allowance.allowance < *amount {
+                return Err(ContractError::InsufficientAllowance {});
+            }
+
+            WasmMsg::Execute {
+                contract_addr: updated_loan.asset_address.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: info.sender.to_string(),
+                    recipient: env.contract.address.to_string(),
+                    amount: *amount,
+                })?,
+                funds: vec![],
+            }
+        }
+        AssetKind::Cw721 { token_id } => WasmMsg::Execute {
+            contract_addr: updated_loan.asset_address.to_string(),
+            msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+                recipient: env.contract.address.to_string(),
+                token_id: token_id.clone(),
+            })?,
+            funds: vec![],
+        },
+    };
+
+    let response = Response::new()
+        .add_message(escrow_msg)
+        .add_attribute("action", "lend_token");
+
+    Ok(response)
 }
 
 fn execute_borrow_token(
@@ -169,29 +239,93 @@ fn execute_borrow_token(
         });
     }
 
-    // Transfer collateral tokens from the borrower to the lender
-    let msg = Cw20ExecuteMsg::Transfer {
-        recipient: loan.lender.to_string(),
-        amount: loan.collateral_amount,
-    };
-    let execute_msg = WasmMsg::Execute {
-        contract_addr: loan.asset_address.to_string(),
-        msg: to_binary(&msg)?,
-        funds: vec![],
-    };
+    // Post collateral into escrow: native coins must be sent exactly with the call,
+    // cw20 collateral is pulled from the borrower via an allowance
+    let mut messages = collateral_escrow_msgs(&env, &info, &loan.collateral_amount)?;
 
-    // Create response
-    let mut response = Response::new().add_messages(vec![execute_msg.into()]);
+    // Only once collateral is confirmed do we hand over the asset and start the clock
+    messages.push(asset_transfer_msg(
+        &loan.asset_address,
+        &loan.asset_kind,
+        &info.sender,
+    )?);
 
-    // Update loan with borrower information
     let updated_loan = Loan {
         borrower: info.sender.clone(),
+        start_time: env.block.time.seconds(),
         ..loan
     };
     LOANS.save(deps.storage, &asset_address, &updated_loan)?;
 
-    // Return response
-    Ok(response)
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "borrow_token"))
+}
+
+/// Verifies the borrower posted exactly the required collateral and returns the
+/// `TransferFrom` messages needed to pull any cw20 portion (denoms prefixed `cw20:`)
+/// into escrow. Native portions must already be attached to the call via `info.funds`.
+fn collateral_escrow_msgs(
+    env: &Env,
+    info: &MessageInfo,
+    collateral_amount: &[Coin],
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let mut native_due: Vec<Coin> = vec![];
+    let mut cw20_due: Vec<Coin> = vec![];
+    for coin in collateral_amount {
+        let bucket = if coin.denom.starts_with("cw20:") {
+            &mut cw20_due
+        } else {
+            &mut native_due
+        };
+        match bucket.iter_mut().find(|due| due.denom == coin.denom) {
+            Some(due) => {
+                due.amount = due
+                    .amount
+                    .checked_add(coin.amount)
+                    .map_err(|_| ContractError::Overflow {})?;
+            }
+            None => bucket.push(coin.clone()),
+        }
+    }
+
+    let matches = native_due.len() == info.funds.len()
+        && native_due.iter().all(|due| {
+            info.funds
+                .iter()
+                .any(|sent| sent.denom == due.denom && sent.amount == due.amount)
+        });
+    if !matches {
+        // Report the full expected collateral, not just the native portion, so a mismatch
+        // on a mixed native+cw20 posting is actually diagnosable.
+        let mut expected = native_due;
+        expected.extend(cw20_due);
+        return Err(ContractError::CollateralMismatch {
+            expected,
+            provided: info.funds.clone(),
+        });
+    }
+
+    cw20_due
+        .iter()
+        .map(|due| {
+            let contract_addr = due
+                .denom
+                .strip_prefix("cw20:")
+                .expect("bucketed by cw20: prefix")
+                .to_string();
+            Ok(WasmMsg::Execute {
+                contract_addr,
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: info.sender.to_string(),
+                    recipient: env.contract.address.to_string(),
+                    amount: due.amount,
+                })?,
+                funds: vec![],
+            }
+            .into())
+        })
+        .collect()
 }
 
 fn execute_return_token(
@@ -213,12 +347,95 @@ fn execute_return_token(
         return Err(ContractError::Unauthorized {});
     }
 
-    // Check if the loan duration has exceeded
-    if env.block.time > loan.start_time + loan.duration {
-        return Err(ContractError::LoanNotFound {});
+    // The borrower can still cure through the grace period; only past it does
+    // liquidation become the lender's only recourse, so return must allow the same window
+    let now = env.block.time.seconds();
+    let return_deadline = loan
+        .start_time
+        .checked_add(loan.duration)
+        .and_then(|t| t.checked_add(loan.grace_period))
+        .ok_or(ContractError::Overflow {})?;
+    if now > return_deadline {
+        return Err(ContractError::ReturnWindowClosed {});
     }
 
-    // Transfer the asset back to the lender with interst amount
+    // Rent accrues per day started since the loan began, capped at the agreed maximum
+    let elapsed = now.saturating_sub(loan.start_time);
+    let days_elapsed = elapsed
+        .checked_add(SECONDS_PER_DAY - 1)
+        .ok_or(ContractError::Overflow {})?
+        / SECONDS_PER_DAY;
+    let days_elapsed = days_elapsed.max(1).min(loan.max_rent_days);
+
+    // Collect the accrued fee per denom, pay it to the lender, and hand the asset back
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut fee_paid: Vec<Coin> = vec![];
+    for daily_fee in &loan.daily_fee_amount {
+        let fee = daily_fee
+            .amount
+            .checked_mul(Uint128::from(days_elapsed))
+            .map_err(|_| ContractError::Overflow {})?;
+
+        if let Some(contract_addr) = daily_fee.denom.strip_prefix("cw20:") {
+            // cw20 denoms never ride in `info.funds`; pull the fee straight from the borrower
+            messages.push(
+                WasmMsg::Execute {
+                    contract_addr: contract_addr.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                        owner: info.sender.to_string(),
+                        recipient: loan.lender.to_string(),
+                        amount: fee,
+                    })?,
+                    funds: vec![],
+                }
+                .into(),
+            );
+        } else {
+            // Native denom: the borrower must have attached at least the computed fee
+            let sent = info
+                .funds
+                .iter()
+                .find(|sent| sent.denom == daily_fee.denom)
+                .map(|sent| sent.amount)
+                .unwrap_or_default();
+            if sent < fee {
+                return Err(ContractError::FeeNotPaid {});
+            }
+            messages.push(transfer_coin_msg(
+                &Coin {
+                    denom: daily_fee.denom.clone(),
+                    amount: fee,
+                },
+                &loan.lender,
+            )?);
+            // Refund any excess attached above the computed fee instead of stranding it
+            if let Some(excess) = sent.checked_sub(fee).ok().filter(|e| !e.is_zero()) {
+                messages.push(transfer_coin_msg(
+                    &Coin {
+                        denom: daily_fee.denom.clone(),
+                        amount: excess,
+                    },
+                    &info.sender,
+                )?);
+            }
+        }
+
+        fee_paid.push(Coin {
+            denom: daily_fee.denom.clone(),
+            amount: fee,
+        });
+    }
+    messages.push(asset_return_msg(
+        &loan.asset_address,
+        &loan.asset_kind,
+        &loan.borrower,
+        &loan.lender,
+    )?);
+
+    // Release the escrowed collateral back to the borrower now that the asset is returned
+    for collateral in &loan.collateral_amount {
+        messages.push(transfer_coin_msg(collateral, &loan.borrower)?);
+    }
 
     // Update the loan status to inactive
     let updated_loan = Loan {
@@ -227,8 +444,183 @@ fn execute_return_token(
     };
     LOANS.save(deps.storage, &asset_address, &updated_loan)?;
 
-    // Return a response indicating success
-    Ok(Response::default())
+    let fee_paid_str = fee_paid
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "return_token")
+        .add_attribute("days_elapsed", days_elapsed.to_string())
+        .add_attribute("fee_paid", fee_paid_str))
+}
+
+/// Builds the message that moves the escrowed real-world asset itself (as opposed to
+/// collateral or fees): a cw20 `Transfer` for fungible assets, a cw721 `TransferNft`
+/// for a single deeded NFT.
+fn asset_transfer_msg(
+    asset_address: &Addr,
+    asset_kind: &AssetKind,
+    recipient: &Addr,
+) -> StdResult<CosmosMsg> {
+    let msg = match asset_kind {
+        AssetKind::Cw20 { amount } => to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: *amount,
+        })?,
+        AssetKind::Cw721 { token_id } => to_binary(&Cw721ExecuteMsg::TransferNft {
+            recipient: recipient.to_string(),
+            token_id: token_id.clone(),
+        })?,
+    };
+    Ok(WasmMsg::Execute {
+        contract_addr: asset_address.to_string(),
+        msg,
+        funds: vec![],
+    }
+    .into())
+}
+
+/// Builds the message that pulls the escrowed real-world asset back from the borrower on
+/// return. By this point the contract no longer holds the asset — `execute_borrow_token`
+/// already handed it to the borrower — so, mirroring the lend-time escrow, the borrower
+/// must have pre-approved the contract (a cw20 allowance, or a cw721 approval) before
+/// calling `ReturnToken`, and we pull the asset rather than push it.
+fn asset_return_msg(
+    asset_address: &Addr,
+    asset_kind: &AssetKind,
+    owner: &Addr,
+    recipient: &Addr,
+) -> StdResult<CosmosMsg> {
+    let msg = match asset_kind {
+        AssetKind::Cw20 { amount } => to_binary(&Cw20ExecuteMsg::TransferFrom {
+            owner: owner.to_string(),
+            recipient: recipient.to_string(),
+            amount: *amount,
+        })?,
+        AssetKind::Cw721 { token_id } => to_binary(&Cw721ExecuteMsg::TransferNft {
+            recipient: recipient.to_string(),
+            token_id: token_id.clone(),
+        })?,
+    };
+    Ok(WasmMsg::Execute {
+        contract_addr: asset_address.to_string(),
+        msg,
+        funds: vec![],
+    }
+    .into())
+}
+
+/// Builds a transfer message for a single `Coin`. Fungible RWA fees/collateral use the
+/// `cw20:<contract_addr>` denom convention; any other denom is sent as a native bank coin.
+fn transfer_coin_msg(coin: &Coin, recipient: &Addr) -> StdResult<CosmosMsg> {
+    if let Some(contract_addr) = coin.denom.strip_prefix("cw20:") {
+        Ok(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: coin.amount,
+            })?,
+            funds: vec![],
+        }
+        .into())
+    } else {
+        Ok(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![coin.clone()],
+        }
+        .into())
+    }
+}
+
+fn execute_liquidate_loan(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_address: Addr,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let loan = LOANS.load(deps.storage, &asset_address)?;
+
+    // Ensure the loan is active and currently borrowed
+    if !loan.is_active || loan.borrower == Addr::unchecked("") {
+        return Err(ContractError::LoanNotFound {});
+    }
+
+    // Only the lender or the arbiter may liquidate
+    if info.sender != loan.lender && info.sender != config.arbiter {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Liquidation is only allowed once the loan is overdue past its grace period
+    let now = env.block.time.seconds();
+    let liquidatable_at = loan
+        .start_time
+        .checked_add(loan.duration)
+        .and_then(|t| t.checked_add(loan.grace_period))
+        .ok_or(ContractError::Overflow {})?;
+    if now <= liquidatable_at {
+        return Err(ContractError::NotLiquidatable {});
+    }
+
+    // Transfer the collateral to the lender permanently
+    let messages = loan
+        .collateral_amount
+        .iter()
+        .map(|coin| transfer_coin_msg(coin, &loan.lender))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let updated_loan = Loan {
+        is_active: false,
+        borrower: Addr::unchecked(""),
+        ..loan
+    };
+    LOANS.save(deps.storage, &asset_address, &updated_loan)?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "liquidate")
+        .add_attribute("asset_address", asset_address.to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Arbiter {} => to_binary(&query_arbiter(deps)?),
+        QueryMsg::GetConfig {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::GetLoan { asset_address } => {
+            to_binary(&LOANS.load(deps.storage, &asset_address)?)
+        }
+        QueryMsg::ListLoans { start_after, limit } => {
+            to_binary(&query_list_loans(deps, start_after, limit)?)
+        }
+    }
+}
+
+fn query_arbiter(deps: Deps) -> StdResult<ArbiterResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ArbiterResponse {
+        arbiter: config.arbiter,
+    })
+}
+
+fn query_list_loans(
+    deps: Deps,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<ListLoansResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let loans = LOANS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, loan)| loan))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ListLoansResponse { loans })
 }
 
 fn execute_withdraw_collateral(
@@ -257,3 +649,105 @@ fn execute_withdraw_collateral(
 
     Ok(response)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::coins;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+
+    fn setup(deps: DepsMut, env: Env) {
+        instantiate(
+            deps,
+            env,
+            mock_info("arbiter", &[]),
+            InstantiateMsg {
+                arbiter: "arbiter".to_string(),
+                recipient: "recipient".to_string(),
+                expiration: None,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn lend_borrow_return_round_trip_pulls_asset_from_borrower() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        setup(deps.as_mut(), env.clone());
+
+        let asset_address = Addr::unchecked("nft_contract");
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("lender", &[]),
+            ExecuteMsg::LendToken {
+                asset_address: asset_address.clone(),
+                asset_kind: AssetKind::Cw721 {
+                    token_id: "1".to_string(),
+                },
+                duration: SECONDS_PER_DAY,
+                collateral_amount: coins(100, "uusd"),
+                daily_fee_amount: coins(5, "uusd"),
+                max_rent_days: 10,
+                grace_period: SECONDS_PER_DAY,
+                ipfs_cid: "cid".to_string(),
+            },
+        )
+        .unwrap();
+
+        let borrow_res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("borrower", &coins(100, "uusd")),
+            ExecuteMsg::BorrowToken {
+                asset_address: asset_address.clone(),
+            },
+        )
+        .unwrap();
+        assert_eq!(borrow_res.messages.len(), 1);
+
+        let return_res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("borrower", &coins(5, "uusd")),
+            ExecuteMsg::ReturnToken {
+                asset_address: asset_address.clone(),
+            },
+        )
+        .unwrap();
+
+        // The asset must be pulled back from the borrower (`TransferNft` executed by the
+        // contract as an approved operator), not pushed as if the contract still held it.
+        let asset_msg = return_res
+            .messages
+            .iter()
+            .find_map(|m| match &m.msg {
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr, msg, ..
+                }) if contract_addr == "nft_contract" => Some(msg.clone()),
+                _ => None,
+            })
+            .expect("asset transfer message present");
+        let expected = to_binary(&Cw721ExecuteMsg::TransferNft {
+            recipient: "lender".to_string(),
+            token_id: "1".to_string(),
+        })
+        .unwrap();
+        assert_eq!(asset_msg, expected);
+
+        // Collateral is released back to the borrower and the loan is closed out.
+        let collateral_returned = return_res.messages.iter().any(|m| {
+            matches!(
+                &m.msg,
+                CosmosMsg::Bank(BankMsg::Send { to_address, amount })
+                    if to_address == "borrower" && amount == &coins(100, "uusd")
+            )
+        });
+        assert!(collateral_returned);
+
+        let loan = LOANS.load(deps.as_ref().storage, &asset_address).unwrap();
+        assert!(!loan.is_active);
+    }
+}
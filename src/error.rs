@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Coin, StdError};
 use cw_utils::Expiration;
 use thiserror::Error;
 
@@ -16,6 +16,8 @@ pub enum ContractError {
     // NotExpired {},
     #[error("Loan not found")]
     LoanNotFound {},
+    #[error("Return window has closed: loan is past its grace period, only liquidation is possible now")]
+    ReturnWindowClosed {},
     #[error("Loan already active")]
     LoanAlreadyActive {},
     #[error("Invalid Duration")]
@@ -26,4 +28,17 @@ pub enum ContractError {
     InvalidMaxRentDays {},
     #[error("Invalid Loan")]
     InvalidLoan { reason: String },
+    #[error("Arithmetic overflow")]
+    Overflow {},
+    #[error("Rent fee not paid: borrower must attach the accrued fee for every native fee denom")]
+    FeeNotPaid {},
+    #[error("Loan is not yet liquidatable: grace period has not elapsed")]
+    NotLiquidatable {},
+    #[error("Lender has not granted the contract a sufficient cw20 allowance to escrow the asset")]
+    InsufficientAllowance {},
+    #[error("Collateral mismatch: expected {expected:?}, provided {provided:?}")]
+    CollateralMismatch {
+        expected: Vec<Coin>,
+        provided: Vec<Coin>,
+    },
 }
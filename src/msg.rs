@@ -2,6 +2,8 @@ use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Addr, Coin};
 use cw_utils::Expiration;
 
+use crate::state::{AssetKind, Config, Loan};
+
 #[cw_serde]
 pub struct InstantiateMsg {
     pub arbiter: String,
@@ -17,9 +19,20 @@ pub struct InstantiateMsg {
 
 #[cw_serde]
 pub enum ExecuteMsg {
+    LendToken {
+        asset_address: Addr,
+        asset_kind: AssetKind,
+        duration: u64,
+        collateral_amount: Vec<Coin>,
+        daily_fee_amount: Vec<Coin>,
+        max_rent_days: u64,
+        grace_period: u64,
+        ipfs_cid: String,
+    },
     BorrowToken { asset_address: Addr },
     ReturnToken { asset_address: Addr },
     WithdrawCollateral { amount: Vec<Coin> },
+    LiquidateLoan { asset_address: Addr },
 }
 
 #[cw_serde]
@@ -28,9 +41,26 @@ pub enum QueryMsg {
     /// Returns a human-readable representation of the arbiter.
     #[returns(ArbiterResponse)]
     Arbiter {},
+    /// Returns the contract's configuration.
+    #[returns(Config)]
+    GetConfig {},
+    /// Returns the loan keyed by `asset_address`.
+    #[returns(Loan)]
+    GetLoan { asset_address: Addr },
+    /// Returns loans in `asset_address` order, paginated.
+    #[returns(ListLoansResponse)]
+    ListLoans {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
 }
 
 #[cw_serde]
 pub struct ArbiterResponse {
     pub arbiter: Addr,
 }
+
+#[cw_serde]
+pub struct ListLoansResponse {
+    pub loans: Vec<Loan>,
+}
@@ -0,0 +1,40 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Coin, Uint128};
+use cw_storage_plus::{Item, Map};
+use cw_utils::Expiration;
+
+#[cw_serde]
+pub struct Config {
+    pub arbiter: Addr,
+    pub recipient: Addr,
+    pub source: Addr,
+    pub expiration: Option<Expiration>,
+}
+
+/// The real-world asset escrowed by a loan: a fungible cw20 amount, or a single cw721 NFT.
+#[cw_serde]
+pub enum AssetKind {
+    Cw20 { amount: Uint128 },
+    Cw721 { token_id: String },
+}
+
+#[cw_serde]
+pub struct Loan {
+    pub lender: Addr,
+    pub borrower: Addr,
+    pub asset_address: Addr,
+    pub asset_kind: AssetKind,
+    pub duration: u64,
+    pub collateral_amount: Vec<Coin>,
+    pub daily_fee_amount: Vec<Coin>,
+    pub max_rent_days: u64,
+    /// Window after `start_time + duration` during which the loan is merely overdue;
+    /// `LiquidateLoan` is only callable once this window has also elapsed.
+    pub grace_period: u64,
+    pub ipfs_cid: String,
+    pub start_time: u64,
+    pub is_active: bool,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const LOANS: Map<&Addr, Loan> = Map::new("loans");